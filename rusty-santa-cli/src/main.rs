@@ -1,11 +1,13 @@
 extern crate colored;
 extern crate env_logger;
+extern crate rand;
 extern crate rprompt;
 extern crate rusty_santa;
 
 use std::process;
 
 use colored::Colorize;
+use rand::random;
 use rusty_santa::{Group, AssignError};
 
 const VERSION: &'static str = env!("CARGO_PKG_VERSION");
@@ -79,7 +81,11 @@ fn main() {
 
     println!("\nGreat! Now we'll draw the names.");
 
-    match group.assign() {
+    // Draw with an explicit seed so a botched reveal can be re-run identically.
+    let seed: u64 = random();
+    println!("(Draw seed: {}. Keep this if you need to re-run the reveal.)", seed);
+
+    match group.assign_with_seed(seed) {
         Ok(assignments) => {
             println!("I'll show a name, first. That person should come to the computer,");
             println!("without other people seeing the screen.");
@@ -23,13 +23,17 @@ extern crate rand;
 
 use std::collections::{HashMap, HashSet};
 
-use rand::{thread_rng, Rng};
+use rand::{thread_rng, Rng, SeedableRng, XorShiftRng};
 
 #[derive(Clone)]
 struct Matrix {
     keys: Vec<String>,
     indexes: HashMap<String, usize>,
     data: Vec<Vec<bool>>,
+
+    /// Optional preference weights, parallel to `data`. Defaults to all zeroes: a pair with no
+    /// recorded preference is neutral, it neither helps nor hurts `assign_optimal`.
+    weights: Vec<Vec<i64>>,
 }
 
 impl Matrix {
@@ -55,6 +59,7 @@ impl Matrix {
             keys: keys,
             indexes: indexes,
             data: data,
+            weights: vec![vec![0; size]; size],
         }
     }
 
@@ -94,6 +99,24 @@ impl Matrix {
         }
     }
 
+    /// Get the preference weight at the specified coordinates. Defaults to 0.
+    ///
+    /// Panics if the x or y keys are invalid.
+    pub fn get_weight(&self, x: &str, y: &str) -> i64 {
+        let ix = self.indexes.get(x).unwrap();
+        let iy = self.indexes.get(y).unwrap();
+        self.weights[*ix][*iy]
+    }
+
+    /// Set the preference weight at coordinates x/y.
+    ///
+    /// Panics if the x or y keys are invalid.
+    pub fn set_weight(&mut self, x: &str, y: &str, weight: i64) {
+        let ix = self.indexes.get(x).unwrap();
+        let iy = self.indexes.get(y).unwrap();
+        self.weights[*ix][*iy] = weight;
+    }
+
     /// Return whether the key is contained in the matrix.
     pub fn contains(&mut self, key: &str) -> bool {
         self.indexes.contains_key(key)
@@ -120,6 +143,22 @@ enum Constraint {
         from: String,
         to: String,
     },
+    Prefer {
+        from: String,
+        to: String,
+        weight: i64,
+    },
+}
+
+/// A single past year's assignment, kept around until it decays past its `years_back` window.
+#[derive(Debug, Clone)]
+struct HistoryEntry {
+    from: String,
+    to: String,
+
+    /// Number of years, counting the current one, this entry still blocks a repeat for.
+    /// Decremented by one on every later `add_history` call and dropped once it reaches 0.
+    expires_in: u32,
 }
 
 /// A group of people that wants to draw names.
@@ -127,6 +166,7 @@ enum Constraint {
 pub struct Group {
     people_set: HashSet<String>,
     constraints: Vec<Constraint>,
+    history: Vec<HistoryEntry>,
 
     /// When trying to resolve group assignments, try up to `max_attempts`
     /// times until giving up.
@@ -139,6 +179,7 @@ impl Group {
         Group {
             people_set: HashSet::new(),
             constraints: vec![],
+            history: vec![],
             max_attempts: 1000,
         }
     }
@@ -164,49 +205,142 @@ impl Group {
         self.add_constraint(constraint);
     }
 
+    /// Record a soft preference: person A would like to gift person B.
+    ///
+    /// This doesn't force the assignment (use [`exclude`](#method.exclude) /
+    /// [`exclude_pair`](#method.exclude_pair) for hard rules), it only feeds into
+    /// [`assign_optimal`](#method.assign_optimal), which picks the complete assignment with the
+    /// highest total preference weight. Higher weights are preferred more strongly; the scale is
+    /// up to the caller.
+    pub fn prefer(&mut self, from: String, to: String, weight: i64) {
+        let constraint = Constraint::Prefer { from: from, to: to, weight: weight };
+        self.add_constraint(constraint);
+    }
+
+    /// Feed one year's past `(from, to)` assignments back in so a repeat gift pairing is
+    /// automatically excluded, without having to call `exclude` for every pair by hand.
+    ///
+    /// `years_back` controls the decay: each `add_history` call ages all previously recorded
+    /// years by one and drops any that have aged past their own `years_back` window, so calling
+    /// this once per year with e.g. `years_back = 2` only ever blocks a repeat from the last two
+    /// years. Internally this doesn't touch `constraints` directly -- the exclusions are applied
+    /// like `Constraint::Exclude` entries when building the assignment matrix.
+    pub fn add_history(&mut self, assignments: Vec<(String, String)>, years_back: u32) {
+        for entry in self.history.iter_mut() {
+            entry.expires_in = entry.expires_in.saturating_sub(1);
+        }
+
+        for (from, to) in assignments {
+            self.history.push(HistoryEntry { from: from, to: to, expires_in: years_back });
+        }
+        self.history.retain(|entry| entry.expires_in > 0);
+    }
+
     /// Return whether the specified name is alread in the group.
     pub fn contains_name(&self, name: &str) -> bool {
         self.people_set.contains(name)
     }
 
-    /// Run the name assignment!
+    /// Run the name assignment, drawing randomness from the thread-local RNG.
     pub fn assign(&self) -> Result<Vec<(String, String)>, AssignError> {
-        // Initialize the random number generator
         let mut rng = thread_rng();
+        self.assign_with_rng(&mut rng)
+    }
 
-        // Shuffle the people
+    /// Run the name assignment using a caller-supplied seed.
+    ///
+    /// This is a convenience wrapper around [`assign_with_rng`](#method.assign_with_rng) that
+    /// builds a small seedable PRNG from the given seed. Reusing the same seed (and the same
+    /// group/constraints) reproduces the exact same draw, which is handy for re-running a
+    /// botched reveal or for writing deterministic tests.
+    pub fn assign_with_seed(&self, seed: u64) -> Result<Vec<(String, String)>, AssignError> {
+        let mut rng = Self::rng_from_seed(seed);
+        self.assign_with_rng(&mut rng)
+    }
+
+    /// Build a seedable PRNG from a `u64` seed.
+    ///
+    /// `XorShiftRng` requires a non-zero, non-uniform seed array, so the `u64` is mixed into
+    /// four `u32` words instead of being split naively.
+    fn rng_from_seed(seed: u64) -> XorShiftRng {
+        let high = (seed >> 32) as u32;
+        let low = seed as u32;
+        XorShiftRng::from_seed([
+            low ^ 0x9e37_79b9,
+            high ^ 0x243f_6a88,
+            low.wrapping_add(0x85a3_08d3),
+            high.wrapping_add(0xb7e1_5162),
+        ])
+    }
+
+    /// Build the gift possibility matrix for the given (already ordered) people, applying all
+    /// constraints registered on this group.
+    ///
+    /// A constraint naming someone who isn't in `people` yields `BadConstraint`; a history entry
+    /// naming such a person is skipped.
+    fn build_matrix(&self, people: &[String]) -> Result<Matrix, AssignError> {
+        let mut matrix = Matrix::new(people.to_vec());
+
+        // Iterate over constraints, apply them to the matrix
+        for constraint in self.constraints.iter() {
+            match constraint {
+                &Constraint::ExcludePair{ ref a, ref b } => {
+                    if !matrix.contains(a) {
+                        return Err(AssignError::BadConstraint(format!("Unknown person \"{}\"", a)));
+                    }
+                    if !matrix.contains(b) {
+                        return Err(AssignError::BadConstraint(format!("Unknown person \"{}\"", b)));
+                    }
+                    matrix.set(a, b, false);
+                    matrix.set(b, a, false);
+                },
+                &Constraint::Exclude { ref from, ref to } => {
+                    if !matrix.contains(from) {
+                        return Err(AssignError::BadConstraint(format!("Unknown person \"{}\"", from)));
+                    }
+                    if !matrix.contains(to) {
+                        return Err(AssignError::BadConstraint(format!("Unknown person \"{}\"", to)));
+                    }
+                    matrix.set(from, to, false);
+                },
+                &Constraint::Prefer { ref from, ref to, weight } => {
+                    if !matrix.contains(from) {
+                        return Err(AssignError::BadConstraint(format!("Unknown person \"{}\"", from)));
+                    }
+                    if !matrix.contains(to) {
+                        return Err(AssignError::BadConstraint(format!("Unknown person \"{}\"", to)));
+                    }
+                    matrix.set_weight(from, to, weight);
+                }
+            }
+        };
+
+        // Apply still-active history entries as directional exclusions. A person who's no
+        // longer in the group (e.g. they left since last year) is simply ignored here, rather
+        // than treated as a bad constraint: historical data naturally outlives group members.
+        for entry in self.history.iter() {
+            if matrix.contains(&entry.from) && matrix.contains(&entry.to) {
+                matrix.set(&entry.from, &entry.to, false);
+            }
+        }
+
+        Ok(matrix)
+    }
+
+    /// Run the name assignment with a caller-supplied random number generator.
+    pub fn assign_with_rng<R: Rng>(&self, rng: &mut R) -> Result<Vec<(String, String)>, AssignError> {
+        // Collect the people in a stable order before shuffling. `people_set` is a `HashSet`,
+        // whose iteration order depends on the randomized per-process hasher state, so without
+        // sorting first the seed wouldn't be the only source of variability: the same seed could
+        // still produce a different permutation in a different process.
         let mut people: Vec<String> = self.people_set.iter().cloned().collect();
+        people.sort();
         rng.shuffle(&mut people);
 
         'attempt: for _ in 0..self.max_attempts {
 
             // Initialize the gift possibility matrix
-            let mut matrix = Matrix::new(people.clone());
-
-            // Iterate over constraints, apply them to the matrix
-            for constraint in self.constraints.iter() {
-                match constraint {
-                    &Constraint::ExcludePair{ ref a, ref b } => {
-                        if !matrix.contains(a) {
-                            return Err(AssignError::BadConstraint(format!("Unknown person \"{}\"", a)));
-                        }
-                        if !matrix.contains(b) {
-                            return Err(AssignError::BadConstraint(format!("Unknown person \"{}\"", b)));
-                        }
-                        matrix.set(a, b, false);
-                        matrix.set(b, a, false);
-                    },
-                    &Constraint::Exclude { ref from, ref to } => {
-                        if !matrix.contains(from) {
-                            return Err(AssignError::BadConstraint(format!("Unknown person \"{}\"", from)));
-                        }
-                        if !matrix.contains(to) {
-                            return Err(AssignError::BadConstraint(format!("Unknown person \"{}\"", to)));
-                        }
-                        matrix.set(from, to, false);
-                    }
-                }
-            };
+            let mut matrix = self.build_matrix(&people)?;
 
             let mut assignments = vec![];
             for person in people.iter() {
@@ -243,6 +377,266 @@ impl Group {
         }
         return Err(AssignError::GivingUp)
     }
+
+    /// Run the name assignment using bipartite maximum matching, drawing randomness from the
+    /// thread-local RNG.
+    ///
+    /// Unlike [`assign`](#method.assign), this never has to give up on a solvable group: it
+    /// proves whether a complete assignment exists instead of retrying a random draw up to
+    /// `max_attempts` times.
+    pub fn assign_exact(&self) -> Result<Vec<(String, String)>, AssignError> {
+        let mut rng = thread_rng();
+        self.assign_exact_with_rng(&mut rng)
+    }
+
+    /// Run the name assignment using bipartite maximum matching and a caller-supplied random
+    /// number generator.
+    ///
+    /// Givers are the left vertex set, receivers the right, with an edge `i -> j` whenever
+    /// `matrix.get(i, j)` is true. Kuhn's augmenting-path algorithm is run to find a maximum
+    /// matching; if it covers every giver, a valid assignment exists. Each giver's candidate
+    /// list is shuffled first so different RNGs/seeds can yield different perfect matchings.
+    pub fn assign_exact_with_rng<R: Rng>(&self, rng: &mut R) -> Result<Vec<(String, String)>, AssignError> {
+        // Collect the people in a stable order before shuffling their adjacency lists below, for
+        // the same reason `assign_with_rng` does: `people_set` is a `HashSet`, whose iteration
+        // order depends on the randomized per-process hasher state, so without sorting first the
+        // seed wouldn't be the only source of variability.
+        let mut people: Vec<String> = self.people_set.iter().cloned().collect();
+        people.sort();
+        let matrix = self.build_matrix(&people)?;
+        let n = people.len();
+
+        let mut adjacency = Self::build_adjacency(&people, &matrix);
+        for candidates in adjacency.iter_mut() {
+            rng.shuffle(candidates);
+        }
+
+        let mut match_to: Vec<Option<usize>> = vec![None; n];
+        for giver in 0..n {
+            let mut visited = vec![false; n];
+            Self::try_kuhn(giver, &adjacency, &mut visited, &mut match_to);
+        }
+
+        if !match_to.iter().all(Option::is_some) {
+            return Err(AssignError::GivingUp);
+        }
+
+        let mut assignments = vec![(String::new(), String::new()); n];
+        for (receiver, giver) in match_to.into_iter().enumerate() {
+            let giver = giver.unwrap();
+            assignments[giver] = (people[giver].clone(), people[receiver].clone());
+        }
+        Ok(assignments)
+    }
+
+    /// Run the best-effort name assignment: instead of failing outright when the constraints
+    /// are over-specified, return the largest set of valid assignments possible plus the givers
+    /// that couldn't be matched.
+    ///
+    /// This computes a maximum matching the same way [`assign_exact`](#method.assign_exact)
+    /// does, but reports a partial result instead of an error when the matching doesn't cover
+    /// every giver. Useful for tight situations where a perfect draw is impossible and the
+    /// caller wants to know exactly who's left over (e.g. to ask the organizer to relax a
+    /// constraint). Like the other `assign_*` methods, still returns `Err(BadConstraint)` if a
+    /// constraint references a name that isn't in the group.
+    pub fn assign_best_effort(&self) -> Result<(Vec<(String, String)>, Vec<String>), AssignError> {
+        // Stable order, same reasoning as in `assign_with_rng`/`assign_exact_with_rng`: a
+        // `HashSet`'s iteration order isn't something callers should be able to observe.
+        let mut people: Vec<String> = self.people_set.iter().cloned().collect();
+        people.sort();
+        let matrix = self.build_matrix(&people)?;
+        let n = people.len();
+
+        let adjacency = Self::build_adjacency(&people, &matrix);
+        let mut match_to: Vec<Option<usize>> = vec![None; n];
+        for giver in 0..n {
+            let mut visited = vec![false; n];
+            Self::try_kuhn(giver, &adjacency, &mut visited, &mut match_to);
+        }
+
+        let mut assignments = Vec::new();
+        let mut matched = vec![false; n];
+        for (receiver, giver) in match_to.into_iter().enumerate() {
+            if let Some(giver) = giver {
+                assignments.push((people[giver].clone(), people[receiver].clone()));
+                matched[giver] = true;
+            }
+        }
+
+        let unmatched = people.iter().cloned()
+            .zip(matched.iter())
+            .filter(|&(_, &is_matched)| !is_matched)
+            .map(|(name, _)| name)
+            .collect();
+
+        Ok((assignments, unmatched))
+    }
+
+    /// Build, for every giver index, the list of receiver indexes they're allowed to draw.
+    fn build_adjacency(people: &[String], matrix: &Matrix) -> Vec<Vec<usize>> {
+        people.iter().map(|giver| {
+            let row = matrix.get_row(giver);
+            (0..row.len()).filter(|&i| row[i]).collect()
+        }).collect()
+    }
+
+    /// Try to find an augmenting path starting at `giver`, claiming a free receiver or
+    /// recursively bumping whoever currently holds one of `giver`'s candidate receivers.
+    ///
+    /// Returns whether `giver` ended up matched; `match_to` is updated in place along the way.
+    fn try_kuhn(giver: usize, adjacency: &[Vec<usize>], visited: &mut [bool], match_to: &mut [Option<usize>]) -> bool {
+        for &receiver in &adjacency[giver] {
+            if visited[receiver] {
+                continue;
+            }
+            visited[receiver] = true;
+            if match_to[receiver].is_none() || Self::try_kuhn(match_to[receiver].unwrap(), adjacency, visited, match_to) {
+                match_to[receiver] = Some(giver);
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Run the name assignment, picking the complete assignment that maximizes total
+    /// preference weight (see [`prefer`](#method.prefer)) among all assignments that satisfy
+    /// the hard `exclude`/`exclude_pair` constraints.
+    ///
+    /// This finds a minimum-cost perfect matching on an `n x n` cost matrix via the Hungarian
+    /// (Kuhn-Munkres) algorithm: forbidden edges get a large cost, preferred edges get the
+    /// negative of their weight (so maximizing preference becomes minimizing cost), and
+    /// everything else costs 0. Returns `GivingUp` only if every complete matching has to use a
+    /// forbidden edge.
+    pub fn assign_optimal(&self) -> Result<Vec<(String, String)>, AssignError> {
+        // Stable order, same reasoning as in `assign_with_rng`/`assign_exact_with_rng`: a
+        // `HashSet`'s iteration order isn't something callers should be able to observe.
+        let mut people: Vec<String> = self.people_set.iter().cloned().collect();
+        people.sort();
+        let matrix = self.build_matrix(&people)?;
+        let n = people.len();
+
+        // The forbidden-edge sentinel has to dominate every possible all-feasible matching, not
+        // just the preferences used in any one test: `prefer()` takes an open `i64`, so a fixed
+        // constant can end up smaller than a legitimate preference weight and let the Hungarian
+        // algorithm "pay" for a forbidden edge instead of taking a valid alternative. Scale it to
+        // the actual input: with at most `n` edges each worth at most `max_weight` in either
+        // direction, any all-feasible matching costs at most `n * max_weight`, and any matching
+        // that uses a forbidden edge costs at least `forbidden_edge_cost - (n - 1) * max_weight`.
+        // Requiring the latter to exceed the former keeps a forbidden edge always worse.
+        //
+        // `prefer()` accepts any `i64` weight, so `max_weight` itself (computed in `i128` to dodge
+        // overflow in `.abs()`) is first clamped down to `edge_cap`, the largest per-edge magnitude
+        // that still leaves room for `2 * n * edge_cap` to fit safely under
+        // `hungarian_min_cost_matching`'s own "unreached" sentinel (`i64::max_value() / 2`) without
+        // overflowing. Every per-edge cost is clamped to that same `edge_cap`, so the two stay
+        // consistent: `forbidden_edge_cost` is always strictly greater than any real edge cost can
+        // ever be, no matter how extreme the input weights are.
+        let edge_cap: i128 = (i64::max_value() as i128 / 4) / (n.max(1) as i128);
+        let max_weight: i128 = (0..n)
+            .flat_map(|i| (0..n).map(move |j| (i, j)))
+            .map(|(i, j)| (matrix.get_weight(&people[i], &people[j]) as i128).abs())
+            .max()
+            .unwrap_or(0)
+            .min(edge_cap);
+        let forbidden_edge_cost = (2 * (n as i128) * max_weight + 1) as i64;
+
+        let mut cost = vec![vec![0i64; n]; n];
+        for i in 0..n {
+            for j in 0..n {
+                cost[i][j] = if matrix.get(&people[i], &people[j]) {
+                    // Clamp rather than panic/wrap on weights whose negation (or magnitude) would
+                    // otherwise not fit within `edge_cap`.
+                    (-(matrix.get_weight(&people[i], &people[j]) as i128))
+                        .max(-max_weight)
+                        .min(max_weight) as i64
+                } else {
+                    forbidden_edge_cost
+                };
+            }
+        }
+
+        let assignment = hungarian_min_cost_matching(&cost);
+        let mut assignments = Vec::with_capacity(n);
+        for (giver, &receiver) in assignment.iter().enumerate() {
+            if cost[giver][receiver] >= forbidden_edge_cost {
+                return Err(AssignError::GivingUp);
+            }
+            assignments.push((people[giver].clone(), people[receiver].clone()));
+        }
+        Ok(assignments)
+    }
+}
+
+/// Solve the assignment problem (minimum-cost perfect matching on a square cost matrix) using
+/// the O(n^3) Hungarian algorithm with potentials and slack arrays.
+///
+/// Returns, for each row index, the assigned column index.
+fn hungarian_min_cost_matching(cost: &[Vec<i64>]) -> Vec<usize> {
+    let n = cost.len();
+    const INF: i64 = i64::max_value() / 2;
+
+    // All arrays below are 1-indexed (index 0 is a sentinel "no row/column"), which is what
+    // keeps the classic formulation of this algorithm free of off-by-one special cases.
+    let mut u = vec![0i64; n + 1];
+    let mut v = vec![0i64; n + 1];
+    let mut p = vec![0usize; n + 1]; // p[j] = row matched to column j, or 0 if unmatched
+    let mut way = vec![0usize; n + 1];
+
+    for i in 1..=n {
+        p[0] = i;
+        let mut j0 = 0usize;
+        let mut minv = vec![INF; n + 1];
+        let mut used = vec![false; n + 1];
+
+        loop {
+            used[j0] = true;
+            let i0 = p[j0];
+            let mut delta = INF;
+            let mut j1 = 0usize;
+            for j in 1..=n {
+                if !used[j] {
+                    let cur = cost[i0 - 1][j - 1] - u[i0] - v[j];
+                    if cur < minv[j] {
+                        minv[j] = cur;
+                        way[j] = j0;
+                    }
+                    if minv[j] < delta {
+                        delta = minv[j];
+                        j1 = j;
+                    }
+                }
+            }
+            for j in 0..=n {
+                if used[j] {
+                    u[p[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    minv[j] -= delta;
+                }
+            }
+            j0 = j1;
+            if p[j0] == 0 {
+                break;
+            }
+        }
+
+        loop {
+            let j1 = way[j0];
+            p[j0] = p[j1];
+            j0 = j1;
+            if j0 == 0 {
+                break;
+            }
+        }
+    }
+
+    let mut assignment = vec![0usize; n];
+    for j in 1..=n {
+        if p[j] != 0 {
+            assignment[p[j] - 1] = j - 1;
+        }
+    }
+    assignment
 }
 
 /// Errors that can happen while assigning names.
@@ -354,8 +748,291 @@ mod tests {
         group.exclude_pair("Sheldon".into(), "Leonard".into());
         group.exclude_pair("Leonard".into(), "Penny".into());
 
-        for i in 0..1000 {
-            group.assign();
+        for seed in 0..1000 {
+            group.assign_with_seed(seed).ok();
+        }
+    }
+
+    /// Test that the same seed always produces the same assignment.
+    #[test]
+    fn assign_with_seed_is_reproducible() {
+        let mut group = Group::new();
+
+        group.add("a".into());
+        group.add("b".into());
+        group.add("c".into());
+        group.add("d".into());
+
+        let first = group.assign_with_seed(1234).unwrap();
+        let second = group.assign_with_seed(1234).unwrap();
+        assert_eq!(first, second);
+    }
+
+    /// The same seed must reproduce the same assignment independent of the order names were
+    /// added in, since `people_set` is a `HashSet` whose iteration order isn't guaranteed to
+    /// follow insertion order (and can otherwise differ across processes).
+    #[test]
+    fn assign_with_seed_is_reproducible_regardless_of_add_order() {
+        let mut forward = Group::new();
+        forward.add("a".into());
+        forward.add("b".into());
+        forward.add("c".into());
+        forward.add("d".into());
+
+        let mut reverse = Group::new();
+        reverse.add("d".into());
+        reverse.add("c".into());
+        reverse.add("b".into());
+        reverse.add("a".into());
+
+        let forward_result = forward.assign_with_seed(1234).unwrap();
+        let reverse_result = reverse.assign_with_seed(1234).unwrap();
+        assert_eq!(forward_result, reverse_result);
+    }
+
+    /// Same as `assign_with_seed_is_reproducible_regardless_of_add_order`, but for
+    /// `assign_exact_with_rng`: identically-seeded RNGs must produce the same matching
+    /// regardless of the order names were added in.
+    #[test]
+    fn assign_exact_with_rng_is_reproducible_regardless_of_add_order() {
+        let mut forward = Group::new();
+        forward.add("a".into());
+        forward.add("b".into());
+        forward.add("c".into());
+        forward.add("d".into());
+
+        let mut reverse = Group::new();
+        reverse.add("d".into());
+        reverse.add("c".into());
+        reverse.add("b".into());
+        reverse.add("a".into());
+
+        let seed = [1u32, 2u32, 3u32, 4u32];
+        let forward_result = forward.assign_exact_with_rng(&mut XorShiftRng::from_seed(seed)).unwrap();
+        let reverse_result = reverse.assign_exact_with_rng(&mut XorShiftRng::from_seed(seed)).unwrap();
+        assert_eq!(forward_result, reverse_result);
+    }
+
+    /// A group constellation with exactly one valid assignment (a -> c, b -> a, c -> b): the
+    /// matching-based `assign_exact` should always find it.
+    #[test]
+    fn assign_exact_finds_existing_matching() {
+        let mut group = Group::new();
+
+        group.add("a".into());
+        group.add("b".into());
+        group.add("c".into());
+
+        group.exclude("a".into(), "b".into());
+        group.exclude("b".into(), "c".into());
+        group.exclude("c".into(), "a".into());
+
+        let assignments = group.assign_exact().unwrap();
+        assert_eq!(assignments.len(), 3);
+
+        let mut receivers: Vec<String> = assignments.iter().map(|&(_, ref to)| to.clone()).collect();
+        receivers.sort();
+        assert_eq!(receivers, vec!["a".to_string(), "b".to_string(), "c".to_string()]);
+    }
+
+    /// A group constellation that has no valid assignment at all: `assign_exact` should report
+    /// this with certainty rather than retrying.
+    #[test]
+    fn assign_exact_detects_infeasible_group() {
+        let mut group = Group::new();
+
+        group.add("a".into());
+        group.add("b".into());
+
+        group.exclude_pair("a".into(), "b".into());
+
+        match group.assign_exact() {
+            Err(AssignError::GivingUp) => {},
+            other => panic!("expected GivingUp, got {:?}", other),
+        }
+    }
+
+    /// `assign_optimal` should pick the one complete assignment that satisfies every preference,
+    /// even though a random draw might easily miss it.
+    #[test]
+    fn assign_optimal_maximizes_preferences() {
+        let mut group = Group::new();
+
+        group.add("a".into());
+        group.add("b".into());
+        group.add("c".into());
+
+        group.prefer("a".into(), "b".into(), 10);
+        group.prefer("b".into(), "c".into(), 10);
+        group.prefer("c".into(), "a".into(), 10);
+
+        let mut assignments = group.assign_optimal().unwrap();
+        assignments.sort();
+        assert_eq!(assignments, vec![
+            ("a".to_string(), "b".to_string()),
+            ("b".to_string(), "c".to_string()),
+            ("c".to_string(), "a".to_string()),
+        ]);
+    }
+
+    /// `assign_optimal` must never cross a hard exclusion, even when doing so would satisfy a
+    /// preference.
+    #[test]
+    fn assign_optimal_respects_hard_exclusions() {
+        let mut group = Group::new();
+
+        group.add("a".into());
+        group.add("b".into());
+        group.add("c".into());
+
+        group.exclude("a".into(), "b".into());
+        group.prefer("a".into(), "b".into(), 1000);
+
+        let assignments = group.assign_optimal().unwrap();
+        assert!(assignments.iter().all(|&(ref from, ref to)| !(from == "a" && to == "b")));
+    }
+
+    /// If every complete matching has to use a forbidden edge, `assign_optimal` gives up instead
+    /// of silently picking an invalid assignment.
+    #[test]
+    fn assign_optimal_detects_infeasible_group() {
+        let mut group = Group::new();
+
+        group.add("a".into());
+        group.add("b".into());
+
+        group.exclude_pair("a".into(), "b".into());
+
+        match group.assign_optimal() {
+            Err(AssignError::GivingUp) => {},
+            other => panic!("expected GivingUp, got {:?}", other),
+        }
+    }
+
+    /// Large preference weights must not overwhelm the forbidden-edge sentinel: a valid
+    /// assignment that avoids the one excluded edge should still be found.
+    #[test]
+    fn assign_optimal_finds_valid_assignment_despite_huge_preferences() {
+        let mut group = Group::new();
+
+        group.add("a".into());
+        group.add("b".into());
+        group.add("c".into());
+
+        group.exclude("a".into(), "b".into());
+        group.prefer("b".into(), "c".into(), 10_000_000);
+        group.prefer("c".into(), "a".into(), 10_000_000);
+
+        let assignments = group.assign_optimal().unwrap();
+        assert!(assignments.iter().all(|&(ref from, ref to)| !(from == "a" && to == "b")));
+    }
+
+    /// Extreme-but-in-contract preference weights (`prefer()` takes a bare `i64`) must not
+    /// overflow the forbidden-edge sentinel math, nor the per-edge cost negation, and a trivially
+    /// solvable group must still come back with a valid assignment rather than `GivingUp`.
+    #[test]
+    fn assign_optimal_handles_extreme_weights_without_overflow() {
+        let mut group = Group::new();
+
+        group.add("a".into());
+        group.add("b".into());
+        group.add("c".into());
+
+        group.prefer("a".into(), "b".into(), i64::max_value() / 2);
+        group.prefer("b".into(), "c".into(), i64::min_value());
+
+        let assignments = group.assign_optimal().unwrap();
+        assert_eq!(assignments.len(), 3);
+    }
+
+    /// A fully solvable group should come back from `assign_best_effort` with everyone matched
+    /// and nobody left over.
+    #[test]
+    fn assign_best_effort_matches_everyone_when_feasible() {
+        let mut group = Group::new();
+
+        group.add("a".into());
+        group.add("b".into());
+        group.add("c".into());
+
+        let (assignments, unmatched) = group.assign_best_effort().unwrap();
+        assert_eq!(assignments.len(), 3);
+        assert!(unmatched.is_empty());
+    }
+
+    /// An over-constrained group (more mutual exclusions than a complete assignment can
+    /// satisfy) should still yield the largest valid partial assignment, naming the leftover
+    /// givers instead of just failing.
+    #[test]
+    fn assign_best_effort_reports_leftover_on_infeasible_group() {
+        let mut group = Group::new();
+
+        group.add("Sheldon".into());
+        group.add("Amy".into());
+
+        group.exclude_pair("Sheldon".into(), "Amy".into());
+
+        let (assignments, unmatched) = group.assign_best_effort().unwrap();
+        assert!(assignments.is_empty());
+        let mut unmatched = unmatched;
+        unmatched.sort();
+        assert_eq!(unmatched, vec!["Amy".to_string(), "Sheldon".to_string()]);
+    }
+
+    /// A constraint referencing a name that isn't in the group should be reported as a
+    /// `BadConstraint`, not panic.
+    #[test]
+    fn assign_best_effort_reports_bad_constraint() {
+        let mut group = Group::new();
+
+        group.add("a".into());
+        group.add("b".into());
+
+        // Typo: "c" was never added to the group.
+        group.exclude("a".into(), "c".into());
+
+        match group.assign_best_effort() {
+            Err(AssignError::BadConstraint(_)) => {},
+            other => panic!("expected BadConstraint, got {:?}", other),
         }
     }
+
+    /// A pairing from last year's history should be excluded on the next draw.
+    #[test]
+    fn add_history_excludes_repeat_pairing() {
+        let mut group = Group::new();
+
+        group.add("a".into());
+        group.add("b".into());
+
+        group.add_history(vec![("a".to_string(), "b".to_string())], 1);
+
+        match group.assign_exact() {
+            Err(AssignError::GivingUp) => {},
+            other => panic!("expected GivingUp, got {:?}", other),
+        }
+    }
+
+    /// A history entry should stop blocking a repeat once it's aged past its `years_back`
+    /// window.
+    #[test]
+    fn add_history_decays_after_years_back() {
+        let mut group = Group::new();
+
+        group.add("a".into());
+        group.add("b".into());
+
+        // This pairing should only block a repeat for 1 year.
+        group.add_history(vec![("a".to_string(), "b".to_string())], 1);
+
+        // Simulate a new year passing with no new history: the exclusion should have decayed.
+        group.add_history(vec![], 1);
+
+        let mut assignments = group.assign_exact().unwrap();
+        assignments.sort();
+        assert_eq!(assignments, vec![
+            ("a".to_string(), "b".to_string()),
+            ("b".to_string(), "a".to_string()),
+        ]);
+    }
 }